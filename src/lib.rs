@@ -3,7 +3,16 @@
 //! This module provides PHP classes for interacting with UmaDB,
 //! following the same patterns as the Python bindings.
 
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use ext_php_rs::binary::Binary;
 use ext_php_rs::prelude::*;
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::noop::NoopTracer;
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use std::os::fd::AsRawFd;
 use umadb_client::UmaDBClient as RustUmaDBClient;
 use umadb_dcb::{
     DCBAppendCondition as RustAppendCondition, DCBError, DCBEvent as RustEvent,
@@ -12,6 +21,68 @@ use umadb_dcb::{
 };
 use uuid::Uuid;
 
+/// Name under which the client's OpenTelemetry tracer is registered.
+const TRACER_NAME: &str = "umadb-php";
+
+/// Set the status (and an `error.type` attribute) on `span` from a
+/// `DCBError`, mirroring the classification in [`dcb_error_to_exception`].
+fn record_error_on_span(span: &mut dyn opentelemetry::trace::Span, err: &DCBError) {
+    let error_type = match err {
+        DCBError::IntegrityError(_) => "integrity_error",
+        DCBError::TransportError(_) => "transport_error",
+        DCBError::Corruption(_) => "corruption",
+        DCBError::Io(_) => "io_error",
+        _ => "error",
+    };
+    span.set_attribute(KeyValue::new("error.type", error_type));
+    span.set_status(Status::error(format!("{:?}", err)));
+}
+
+/// Extracts a single `traceparent` header for [`TraceContextPropagator`].
+struct TraceparentCarrier<'a>(Option<&'a str>);
+
+impl<'a> Extractor for TraceparentCarrier<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        if key == "traceparent" {
+            self.0
+        } else {
+            None
+        }
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        vec!["traceparent"]
+    }
+}
+
+/// Build the parent span context for an RPC, stitching in an incoming W3C
+/// `traceparent` header when the caller provided one so that PHP request
+/// traces connect to the event-store spans.
+fn context_from_traceparent(traceparent: Option<&str>) -> Context {
+    match traceparent {
+        Some(_) => TraceContextPropagator::new().extract(&TraceparentCarrier(traceparent)),
+        None => Context::current(),
+    }
+}
+
+/// Start a span on `tracer`, or a no-op span if instrumentation isn't
+/// configured for this `Client` instance.
+fn start_span(
+    tracer: &Option<opentelemetry_sdk::trace::Tracer>,
+    name: &'static str,
+    cx: &Context,
+) -> Box<dyn Span> {
+    match tracer {
+        Some(tracer) => Box::new(
+            tracer
+                .span_builder(name)
+                .with_kind(SpanKind::Client)
+                .start_with_context(tracer, cx),
+        ),
+        None => Box::new(NoopTracer::new().start(name)),
+    }
+}
+
 // ============================================================================
 // Error Handling
 // ============================================================================
@@ -69,23 +140,72 @@ impl Event {
     ///
     /// # Parameters
     /// - `event_type` - The event type identifier
-    /// - `data` - Binary event data (string in PHP)
+    /// - `data` - Raw binary event data (a PHP string, treated as raw bytes
+    ///   rather than UTF-8 text)
     /// - `tags` - Optional array of tags
     /// - `uuid` - Optional UUID string
     pub fn __construct(
         event_type: String,
-        data: String,
+        data: Binary<u8>,
         tags: Option<Vec<String>>,
         uuid: Option<String>,
     ) -> Self {
         Self {
             event_type,
-            data: data.into_bytes(),
+            data: data.to_vec(),
             tags: tags.unwrap_or_default(),
             uuid,
         }
     }
 
+    /// Create an Event from hex-encoded binary data.
+    ///
+    /// # Throws
+    /// - UmaDBException if `hex` is not valid hex
+    pub fn from_hex(
+        event_type: String,
+        hex: String,
+        tags: Option<Vec<String>>,
+        uuid: Option<String>,
+    ) -> PhpResult<Self> {
+        let data = hex::decode(hex).map_err(|e| {
+            PhpException::default(format!(
+                "UmaDB\\Exception\\UmaDBException: invalid hex data: {}",
+                e
+            ))
+        })?;
+        Ok(Self {
+            event_type,
+            data,
+            tags: tags.unwrap_or_default(),
+            uuid,
+        })
+    }
+
+    /// Create an Event from base64-encoded binary data.
+    ///
+    /// # Throws
+    /// - UmaDBException if `base64` is not valid base64
+    pub fn from_base64(
+        event_type: String,
+        base64: String,
+        tags: Option<Vec<String>>,
+        uuid: Option<String>,
+    ) -> PhpResult<Self> {
+        let data = general_purpose::STANDARD.decode(base64).map_err(|e| {
+            PhpException::default(format!(
+                "UmaDB\\Exception\\UmaDBException: invalid base64 data: {}",
+                e
+            ))
+        })?;
+        Ok(Self {
+            event_type,
+            data,
+            tags: tags.unwrap_or_default(),
+            uuid,
+        })
+    }
+
     /// Get the event type
     #[php(getter)]
     pub fn get_event_type(&self) -> String {
@@ -93,11 +213,30 @@ impl Event {
     }
 
     /// Get the event data as a string
+    ///
+    /// Non-UTF-8 payloads are lossily converted; use `getRawData`,
+    /// `getDataHex`, or `getDataBase64` to access the bytes without loss.
     #[php(getter)]
     pub fn get_data(&self) -> String {
         String::from_utf8_lossy(&self.data).to_string()
     }
 
+    /// Get the raw event data as a binary-safe PHP string, with no UTF-8
+    /// conversion or loss.
+    pub fn get_raw_data(&self) -> Binary<u8> {
+        Binary::from(self.data.clone())
+    }
+
+    /// Get the event data hex-encoded, safe for logging or test vectors.
+    pub fn get_data_hex(&self) -> String {
+        hex::encode(&self.data)
+    }
+
+    /// Get the event data base64-encoded, safe for logging or test vectors.
+    pub fn get_data_base64(&self) -> String {
+        general_purpose::STANDARD.encode(&self.data)
+    }
+
     /// Get the tags
     #[php(getter)]
     pub fn get_tags(&self) -> Vec<String> {
@@ -368,11 +507,23 @@ impl From<AppendCondition> for RustAppendCondition {
 /// $client = new UmaDB\Client("http://localhost:50051");
 /// $head = $client->head();
 /// ```
+///
+/// Passing `tracing_endpoint` enables OpenTelemetry instrumentation scoped to
+/// this `Client` instance: every `read`, `append`, and `head` call emits a
+/// span (operation name, query item/event counts, byte sizes, resulting
+/// head/position, and error status on failure) exported to a Jaeger agent.
+/// Without it, instrumentation is a no-op. Instrumentation never touches
+/// `opentelemetry::global`, so one `Client`'s tracing configuration cannot
+/// leak into another's.
 #[php_class]
 #[php(name = "UmaDB\\Client")]
 pub struct Client {
     /// Internal Rust client
     inner: umadb_client::SyncUmaDBClient,
+    /// Per-instance tracer; `None` means instrumentation is a no-op.
+    tracer: Option<opentelemetry_sdk::trace::Tracer>,
+    /// Owns `tracer`'s exporter pipeline; shut down when the client is dropped.
+    tracer_provider: Option<opentelemetry_sdk::trace::TracerProvider>,
 }
 
 #[php_impl]
@@ -383,14 +534,37 @@ impl Client {
     /// - `url` - Server URL (e.g., "http://localhost:50051" or "https://server:50051")
     /// - `ca_path` - Optional path to CA certificate file for TLS
     /// - `batch_size` - Optional batch size for reading events
+    /// - `tracing_endpoint` - Optional Jaeger agent endpoint (e.g.
+    ///   "localhost:6831"); when set, initializes a Jaeger agent exporter and
+    ///   a tracer named after the extension, owned by this client instance.
+    ///   When unset, instrumentation is a no-op.
     ///
     /// # Throws
     /// - TransportException if connection fails
+    /// - UmaDBException if tracing initialization fails
     pub fn __construct(
         url: String,
         ca_path: Option<String>,
         batch_size: Option<u32>,
+        tracing_endpoint: Option<String>,
     ) -> PhpResult<Self> {
+        let (tracer, tracer_provider) = if let Some(endpoint) = tracing_endpoint {
+            let provider = opentelemetry_jaeger::new_agent_pipeline()
+                .with_endpoint(endpoint)
+                .with_service_name(TRACER_NAME)
+                .build_simple()
+                .map_err(|e| {
+                    PhpException::default(format!(
+                        "UmaDB\\Exception\\UmaDBException: failed to initialize tracing: {}",
+                        e
+                    ))
+                })?;
+            let tracer = provider.tracer(TRACER_NAME);
+            (Some(tracer), Some(provider))
+        } else {
+            (None, None)
+        };
+
         let mut client_builder = RustUmaDBClient::new(url);
 
         if let Some(path) = ca_path {
@@ -403,7 +577,11 @@ impl Client {
 
         let inner = client_builder.connect().map_err(dcb_error_to_exception)?;
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            tracer,
+            tracer_provider,
+        })
     }
 
     /// Read events from the event store.
@@ -413,13 +591,16 @@ impl Client {
     /// - `start` - Optional starting position
     /// - `backwards` - Read backwards from start position
     /// - `limit` - Optional maximum number of events to return
-    /// - `subscribe` - Subscribe to new events (streaming)
+    /// - `subscribe` - Must not be `true`; a live subscription never ends and
+    ///   would block forever here. Use `subscribe()` instead.
+    /// - `traceparent` - Optional incoming W3C trace context to stitch this
+    ///   call's span into the caller's trace
     ///
     /// # Returns
     /// Array of SequencedEvent objects
     ///
     /// # Throws
-    /// - UmaDBException on error
+    /// - UmaDBException on error, or if `subscribe` is `true`
     pub fn read(
         &self,
         query: Option<&Query>,
@@ -427,25 +608,91 @@ impl Client {
         backwards: Option<bool>,
         limit: Option<u32>,
         subscribe: Option<bool>,
+        traceparent: Option<String>,
     ) -> PhpResult<Vec<SequencedEvent>> {
+        if subscribe.unwrap_or(false) {
+            return Err(PhpException::default(
+                "UmaDB\\Exception\\UmaDBException: read() cannot be used with subscribe=true \
+                 because a live subscription never ends; use Client::subscribe() instead"
+                    .to_string(),
+            ));
+        }
+
+        let cx = context_from_traceparent(traceparent.as_deref());
+        let mut span = start_span(&self.tracer, "umadb.read", &cx);
+        span.set_attribute(KeyValue::new(
+            "umadb.query_items",
+            query.map(|q| q.items.len()).unwrap_or(0) as i64,
+        ));
+
         let rust_query = query.map(|q| q.clone().into());
         let backwards = backwards.unwrap_or(false);
-        let subscribe = subscribe.unwrap_or(false);
 
-        let mut response = self
-            .inner
-            .read(rust_query, start, backwards, limit, subscribe)
-            .map_err(dcb_error_to_exception)?;
+        let mut response = match self.inner.read(rust_query, start, backwards, limit, false) {
+            Ok(response) => response,
+            Err(err) => {
+                record_error_on_span(&mut *span, &err);
+                return Err(dcb_error_to_exception(err));
+            }
+        };
 
         let mut events = Vec::new();
+        let mut bytes = 0usize;
         for result in response.by_ref() {
-            let seq_event = result.map_err(dcb_error_to_exception)?;
-            events.push(seq_event.into());
+            match result {
+                Ok(seq_event) => {
+                    bytes += seq_event.event.data.len();
+                    events.push(seq_event.into());
+                }
+                Err(err) => {
+                    record_error_on_span(&mut *span, &err);
+                    return Err(dcb_error_to_exception(err));
+                }
+            }
         }
 
+        span.set_attribute(KeyValue::new("umadb.event_count", events.len() as i64));
+        span.set_attribute(KeyValue::new("umadb.bytes", bytes as i64));
+
         Ok(events)
     }
 
+    /// Open a live subscription to events matching `query`, starting at
+    /// `start`.
+    ///
+    /// Unlike `read`, the returned `Subscription` never ends on its own.
+    /// Drive it with `Subscription::next`/`tryNext`, optionally multiplexing
+    /// its `getFd()` with `stream_select()` in a PHP event loop.
+    ///
+    /// # Parameters
+    /// - `query` - Optional Query object to filter events
+    /// - `start` - Optional starting position
+    ///
+    /// # Returns
+    /// A `Subscription` owning the live event stream
+    ///
+    /// # Throws
+    /// - UmaDBException on error
+    pub fn subscribe(&self, query: Option<&Query>, start: Option<u64>) -> PhpResult<Subscription> {
+        let mut span = start_span(&self.tracer, "umadb.subscribe", &Context::current());
+        span.set_attribute(KeyValue::new(
+            "umadb.query_items",
+            query.map(|q| q.items.len()).unwrap_or(0) as i64,
+        ));
+
+        let rust_query = query.map(|q| q.clone().into());
+
+        let response = match self.inner.read(rust_query, start, false, None, true) {
+            Ok(response) => response,
+            Err(err) => {
+                record_error_on_span(&mut *span, &err);
+                return Err(dcb_error_to_exception(err));
+            }
+        };
+
+        Ok(Subscription { inner: response })
+    }
+
     /// Get the current head position of the event store.
     ///
     /// # Returns
@@ -454,7 +701,20 @@ impl Client {
     /// # Throws
     /// - UmaDBException on error
     pub fn head(&self) -> PhpResult<Option<u64>> {
-        self.inner.head().map_err(dcb_error_to_exception)
+        let mut span = start_span(&self.tracer, "umadb.head", &Context::current());
+
+        match self.inner.head() {
+            Ok(head) => {
+                if let Some(position) = head {
+                    span.set_attribute(KeyValue::new("umadb.position", position as i64));
+                }
+                Ok(head)
+            }
+            Err(err) => {
+                record_error_on_span(&mut *span, &err);
+                Err(dcb_error_to_exception(err))
+            }
+        }
     }
 
     /// Append events to the event store.
@@ -462,6 +722,8 @@ impl Client {
     /// # Parameters
     /// - `events` - Array of Event objects to append
     /// - `condition` - Optional AppendCondition for optimistic concurrency control
+    /// - `traceparent` - Optional incoming W3C trace context to stitch this
+    ///   call's span into the caller's trace
     ///
     /// # Returns
     /// Position of the last appended event
@@ -473,18 +735,106 @@ impl Client {
         &self,
         events: Vec<&Event>,
         condition: Option<&AppendCondition>,
+        traceparent: Option<String>,
     ) -> PhpResult<u64> {
+        let cx = context_from_traceparent(traceparent.as_deref());
+        let mut span = start_span(&self.tracer, "umadb.append", &cx);
+
+        let bytes: usize = events.iter().map(|e| e.data.len()).sum();
+        span.set_attribute(KeyValue::new("umadb.event_count", events.len() as i64));
+        span.set_attribute(KeyValue::new("umadb.bytes", bytes as i64));
+
         let rust_events: Result<Vec<RustEvent>, PhpException> = events
             .iter()
             .map(|e| e.to_dcb_event())
             .collect();
 
-        let rust_events = rust_events?;
+        let rust_events = match rust_events {
+            Ok(rust_events) => rust_events,
+            Err(err) => {
+                span.set_status(Status::error("invalid event data"));
+                return Err(err);
+            }
+        };
         let rust_condition = condition.map(|c| c.clone().into());
 
-        self.inner
-            .append(rust_events, rust_condition)
-            .map_err(dcb_error_to_exception)
+        match self.inner.append(rust_events, rust_condition) {
+            Ok(position) => {
+                span.set_attribute(KeyValue::new("umadb.position", position as i64));
+                Ok(position)
+            }
+            Err(err) => {
+                record_error_on_span(&mut *span, &err);
+                Err(dcb_error_to_exception(err))
+            }
+        }
+    }
+}
+
+impl Drop for Client {
+    /// Shut down this client's own tracer provider (if tracing was
+    /// configured) so its exporter doesn't outlive the client.
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+// ============================================================================
+// Subscription Class
+// ============================================================================
+
+/// A live stream of events opened by `Client::subscribe`.
+///
+/// The subscription owns the underlying response stream for its entire
+/// lifetime and is therefore not `Clone`. Drive it by calling `next()`
+/// (blocking) or `tryNext()` (non-blocking) in a loop; `getFd()` exposes the
+/// transport's readable file descriptor so PHP userland can `stream_select()`
+/// across several subscriptions and its own sockets in one event loop,
+/// calling `tryNext()` only once the fd signals readable.
+#[php_class]
+#[php(name = "UmaDB\\Subscription")]
+pub struct Subscription {
+    /// The underlying live response stream
+    inner: umadb_client::ReadResponse,
+}
+
+#[php_impl]
+impl Subscription {
+    /// Block until the next event arrives and return it, or `null` once the
+    /// stream ends.
+    ///
+    /// # Throws
+    /// - UmaDBException on error
+    pub fn next(&mut self) -> PhpResult<Option<SequencedEvent>> {
+        match self.inner.next() {
+            Some(result) => Ok(Some(result.map_err(dcb_error_to_exception)?.into())),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the next event if one is already buffered, or `null`
+    /// immediately without blocking.
+    ///
+    /// # Throws
+    /// - UmaDBException on error
+    pub fn try_next(&mut self) -> PhpResult<Option<SequencedEvent>> {
+        match self.inner.try_next() {
+            Some(result) => Ok(Some(result.map_err(dcb_error_to_exception)?.into())),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the underlying transport's readable file descriptor, for use
+    /// with `stream_select()` in a PHP event loop.
+    pub fn get_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+
+    /// Close the subscription and release the underlying connection.
+    pub fn close(&mut self) {
+        self.inner.close();
     }
 }
 
@@ -502,4 +852,5 @@ pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
         .class::<Query>()
         .class::<AppendCondition>()
         .class::<Client>()
+        .class::<Subscription>()
 }